@@ -154,7 +154,10 @@ pub async fn agent_get_user_messages(
 pub async fn agent_clear_session(
     session_id: String,
     state: tauri::State<'_, Arc<AgentStateManager>>,
+    watchers: tauri::State<'_, Arc<crate::tools::watch_operations::WatcherRegistry>>,
 ) -> Result<(), String> {
+    // Tear down any watcher for this session so its background thread doesn't leak.
+    watchers.stop(&session_id);
     state.clear_session(&session_id);
     Ok(())
 }
@@ -10,6 +10,9 @@ mod tools;
 // Agent System - Autonomous AI operation
 mod agent;
 
+// Tool permission/capability layer
+mod policy;
+
 use std::sync::Arc;
 use tauri::Manager;
 
@@ -19,8 +22,27 @@ pub fn run() {
     // Initialize agent state manager
     let agent_state = Arc::new(agent::AgentStateManager::new());
 
+    // Initialize the tool policy; fs:read/fs:write/command:run/net:fetch stay
+    // permissive out of the box (the latter two via ToolPolicy's baseline rules), but
+    // fs:delete default-denies until a config file explicitly grants it.
+    let tool_policy = Arc::new(policy::ToolPolicy::new());
+
+    // Caches directory walks so repeated list/search calls on the same root don't
+    // re-crawl the filesystem within a session.
+    let crawl_cache = Arc::new(tools::crawl_cache::CrawlCache::new());
+
+    // Active filesystem watchers, keyed by session_id.
+    let watcher_registry = Arc::new(tools::watch_operations::WatcherRegistry::new());
+
+    // Shell backends tool_run_command is permitted to spawn.
+    let shell_allowlist = Arc::new(tools::command_operations::ShellAllowlist::new());
+
     tauri::Builder::default()
         .manage(agent_state)
+        .manage(tool_policy)
+        .manage(crawl_cache)
+        .manage(watcher_registry)
+        .manage(shell_allowlist)
         // If ENABLE_DEVTOOLS env var is set, open the main window devtools on startup
         .setup(|app| {
             if std::env::var("ENABLE_DEVTOOLS").is_ok() {
@@ -30,6 +52,18 @@ pub fn run() {
                 }
             }
 
+            // Best-effort load of a user-supplied policy config; a missing file just
+            // leaves the default policy described above in place.
+            if let Ok(config_dir) = app.path().app_config_dir() {
+                let policy_path = config_dir.join("tool_policy.json");
+                if policy_path.exists() {
+                    let tool_policy = app.state::<Arc<policy::ToolPolicy>>();
+                    if let Err(e) = tool_policy.load_from_file(&policy_path) {
+                        eprintln!("Failed to load tool policy: {}", e);
+                    }
+                }
+            }
+
             Ok(())
         })
         .plugin(tauri_plugin_dialog::init())
@@ -51,14 +85,24 @@ pub fn run() {
             tools::tool_create_directory,
             // Search operations
             tools::tool_search_files,
+            tools::tool_search_content,
             // Command operations
             tools::tool_run_command,
+            tools::tool_shell_allowlist_get,
+            tools::tool_shell_allowlist_set,
             // File check operations
             tools::tool_file_check,
             // Diff operations
             tools::tool_apply_diff,
             // Fetch operations
             tools::tool_fetch,
+            tools::tool_fetch_stream,
+            // Watch operations
+            tools::tool_watch_start,
+            tools::tool_watch_stop,
+            // Tool policy operations
+            policy::tool_policy_get,
+            policy::tool_policy_reload,
             // Agent operations
             agent::agent_update_task_progress,
             agent::agent_send_user_message,
@@ -0,0 +1,298 @@
+/// Tool capability/permission layer
+///
+/// Gates every `tool_*` command by path and operation, modeled on Tauri's own
+/// permission/capability design. Rules are loaded from a JSON config file and can be
+/// hot-reloaded at runtime so the frontend can show the user what the agent is
+/// permitted to do for the current session.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A single gated operation an agent tool may attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    #[serde(rename = "fs:read")]
+    FsRead,
+    #[serde(rename = "fs:write")]
+    FsWrite,
+    #[serde(rename = "fs:delete")]
+    FsDelete,
+    #[serde(rename = "command:run")]
+    CommandRun,
+    #[serde(rename = "net:fetch")]
+    NetFetch,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Capability::FsRead => "fs:read",
+            Capability::FsWrite => "fs:write",
+            Capability::FsDelete => "fs:delete",
+            Capability::CommandRun => "command:run",
+            Capability::NetFetch => "net:fetch",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// One allow/deny rule. Rules are evaluated in order; the first rule whose
+/// capability and scope (path glob, or host list for `net:fetch`) match wins. If no
+/// rule matches, `fs:read`/`fs:write` fall back to allowed (today's unrestricted
+/// behavior for non-destructive access). `command:run`/`net:fetch` also work out of
+/// the box, via the baseline allow-all rules in `ToolPolicy::default_config`, but
+/// `fs:delete` has no such baseline and default-denies, mirroring how Tauri's own
+/// capability system treats anything not explicitly granted: deleting files is
+/// blocked rather than silently open until a policy file grants it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub capability: Capability,
+    /// Glob scoping the rule to a path (fs:* and command:run). `None` matches any path.
+    #[serde(default)]
+    pub path_glob: Option<String>,
+    /// Host allowlist scoping the rule (net:fetch only). `None` matches any host.
+    #[serde(default)]
+    pub hosts: Option<Vec<String>>,
+    pub allow: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// Structured denial returned when a command is blocked by the active policy.
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionDenied {
+    pub capability: String,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Permission denied for {} on '{}': {}",
+            self.capability, self.path, self.message
+        )
+    }
+}
+
+/// Managed state holding the active policy, mirroring `AgentStateManager`.
+pub struct ToolPolicy {
+    config: Mutex<PolicyConfig>,
+    config_path: Mutex<Option<PathBuf>>,
+}
+
+impl ToolPolicy {
+    /// Starts from `default_config()`: `fs:read`/`fs:write` are permissive by omission
+    /// (matching today's unrestricted behavior), and a baseline allow-all rule keeps
+    /// `command:run`/`net:fetch` working out of the box on a fresh install with no
+    /// `tool_policy.json` present yet. `fs:delete` has no baseline rule and so
+    /// default-denies until a config file is loaded via `load_from_file` to grant it.
+    pub fn new() -> Self {
+        Self {
+            config: Mutex::new(Self::default_config()),
+            config_path: Mutex::new(None),
+        }
+    }
+
+    /// Baseline rules in effect before any `tool_policy.json` is loaded. Grants
+    /// `command:run`/`net:fetch` unconditionally so those core agent features keep
+    /// working out of the box; a later `load_from_file` fully replaces this set, so a
+    /// hand-written policy that wants to restrict them just needs to say so explicitly.
+    fn default_config() -> PolicyConfig {
+        PolicyConfig {
+            rules: vec![
+                PolicyRule {
+                    capability: Capability::CommandRun,
+                    path_glob: None,
+                    hosts: None,
+                    allow: true,
+                },
+                PolicyRule {
+                    capability: Capability::NetFetch,
+                    path_glob: None,
+                    hosts: None,
+                    allow: true,
+                },
+            ],
+        }
+    }
+
+    /// Load (or reload) rules from a JSON config file. Remembers the path so a later
+    /// `reload()` with no argument re-reads the same file.
+    pub fn load_from_file(&self, path: &Path) -> Result<PolicyConfig, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read policy file '{}': {}", path.display(), e))?;
+        let parsed: PolicyConfig = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse policy file '{}': {}", path.display(), e))?;
+
+        *self.config.lock().map_err(|e| e.to_string())? = parsed.clone();
+        *self.config_path.lock().map_err(|e| e.to_string())? = Some(path.to_path_buf());
+
+        Ok(parsed)
+    }
+
+    /// Re-read the last-loaded config file from disk, if any was ever loaded.
+    pub fn reload(&self) -> Result<PolicyConfig, String> {
+        let path = self
+            .config_path
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
+        match path {
+            Some(path) => self.load_from_file(&path),
+            None => Ok(self.snapshot()),
+        }
+    }
+
+    pub fn snapshot(&self) -> PolicyConfig {
+        self.config.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    /// Canonicalize `path` as best-effort (falling back to the raw path if it doesn't
+    /// exist yet, e.g. a file about to be created) and check it against `capability`.
+    pub fn check_path(&self, capability: Capability, path: &Path) -> Result<(), PermissionDenied> {
+        let resolved = resolve_best_effort(path);
+        let path_str = resolved.to_string_lossy().replace('\\', "/");
+
+        let config = self.config.lock().unwrap_or_else(|e| e.into_inner());
+        for rule in config.rules.iter().filter(|r| r.capability == capability) {
+            let matches = match &rule.path_glob {
+                Some(pattern) => glob::Pattern::new(pattern)
+                    .map(|p| p.matches(&path_str))
+                    .unwrap_or(false),
+                None => true,
+            };
+            if matches {
+                return if rule.allow {
+                    Ok(())
+                } else {
+                    Err(PermissionDenied {
+                        capability: capability.to_string(),
+                        path: path_str,
+                        message: "blocked by policy rule".to_string(),
+                    })
+                };
+            }
+        }
+
+        if is_destructive(capability) {
+            return Err(PermissionDenied {
+                capability: capability.to_string(),
+                path: path_str,
+                message: "no policy rule grants this capability (default-deny)".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check a `net:fetch` request against the host allowlist rules.
+    pub fn check_host(&self, url: &str) -> Result<(), PermissionDenied> {
+        let host = extract_host(url);
+
+        let config = self.config.lock().unwrap_or_else(|e| e.into_inner());
+        for rule in config
+            .rules
+            .iter()
+            .filter(|r| r.capability == Capability::NetFetch)
+        {
+            let matches = match (&rule.hosts, &host) {
+                (Some(hosts), Some(host)) => hosts.iter().any(|h| h.to_lowercase() == *host),
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            if matches {
+                return if rule.allow {
+                    Ok(())
+                } else {
+                    Err(PermissionDenied {
+                        capability: Capability::NetFetch.to_string(),
+                        path: url.to_string(),
+                        message: "blocked by policy rule".to_string(),
+                    })
+                };
+            }
+        }
+
+        Err(PermissionDenied {
+            capability: Capability::NetFetch.to_string(),
+            path: url.to_string(),
+            message: "no policy rule grants this capability (default-deny)".to_string(),
+        })
+    }
+}
+
+/// `fs:delete`, `command:run`, and `net:fetch` default-deny when no rule matches;
+/// `fs:read`/`fs:write` stay permissive until a policy file explicitly restricts them.
+fn is_destructive(capability: Capability) -> bool {
+    matches!(
+        capability,
+        Capability::FsDelete | Capability::CommandRun | Capability::NetFetch
+    )
+}
+
+/// Pull the host out of a URL without a full parser, good enough for allowlist checks.
+fn extract_host(url: &str) -> Option<String> {
+    let after_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = after_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or(after_scheme);
+    let host_and_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = if host_and_port.starts_with('[') {
+        // IPv6 literal, e.g. [::1]:8080
+        match host_and_port.find(']') {
+            Some(idx) => host_and_port[..=idx].to_string(),
+            None => host_and_port.to_string(),
+        }
+    } else {
+        host_and_port.split(':').next().unwrap_or(host_and_port).to_string()
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+fn resolve_best_effort(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    if let Some(parent) = path.parent() {
+        if let Ok(canonical_parent) = parent.canonicalize() {
+            if let Some(file_name) = path.file_name() {
+                return canonical_parent.join(file_name);
+            }
+            return canonical_parent;
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Tauri command so the frontend can show the user what the agent is currently
+/// permitted to do.
+#[tauri::command]
+pub fn tool_policy_get(state: tauri::State<'_, std::sync::Arc<ToolPolicy>>) -> Result<PolicyConfig, String> {
+    Ok(state.snapshot())
+}
+
+/// Tauri command to hot-reload the policy, optionally from a new file path.
+#[tauri::command]
+pub fn tool_policy_reload(
+    path: Option<String>,
+    state: tauri::State<'_, std::sync::Arc<ToolPolicy>>,
+) -> Result<PolicyConfig, String> {
+    match path {
+        Some(path) => state.load_from_file(Path::new(&path)),
+        None => state.reload(),
+    }
+}
@@ -1,66 +1,315 @@
 /**
  * Command Execution Tools
- * 
- * Rust implementation for command execution:
- * - run_command: Execute PowerShell commands with UTF-8 encoding
+ *
+ * Rust implementation for command execution, backed by a pluggable `Shell` trait so
+ * the tool isn't locked to `powershell.exe`:
+ * - run_command: Execute a command through PowerShell, cmd, bash/sh, or directly
  */
 
-use std::process::Command;
+use crate::policy::{Capability, ToolPolicy};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A backend capable of turning a logical command + args into a spawnable process.
+trait Shell: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command;
+}
+
+struct PowerShell;
+impl Shell for PowerShell {
+    fn name(&self) -> &'static str {
+        "powershell"
+    }
+
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command {
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args(&[
+            "-NoProfile",
+            "-NoLogo",
+            "-NonInteractive",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-Command",
+            &format!(
+                "[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; {}",
+                join_command(command, args)
+            ),
+        ]);
+        cmd.current_dir(working_dir);
+        cmd
+    }
+}
+
+struct Cmd;
+impl Shell for Cmd {
+    fn name(&self) -> &'static str {
+        "cmd"
+    }
+
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command {
+        let mut cmd = Command::new("cmd.exe");
+        // `chcp 65001` switches the console codepage to UTF-8 before running the command.
+        cmd.args(&[
+            "/C",
+            &format!("chcp 65001>nul & {}", join_command(command, args)),
+        ]);
+        cmd.current_dir(working_dir);
+        cmd
+    }
+}
+
+struct Bash;
+impl Shell for Bash {
+    fn name(&self) -> &'static str {
+        "bash"
+    }
+
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command {
+        let mut cmd = Command::new("bash");
+        cmd.args(&["-lc", &join_command(command, args)]);
+        cmd.current_dir(working_dir);
+        cmd.env("LANG", "C.UTF-8");
+        cmd
+    }
+}
+
+struct Sh;
+impl Shell for Sh {
+    fn name(&self) -> &'static str {
+        "sh"
+    }
+
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.args(&["-c", &join_command(command, args)]);
+        cmd.current_dir(working_dir);
+        cmd.env("LANG", "C.UTF-8");
+        cmd
+    }
+}
+
+/// Generic program-plus-args executor: no shell wrapping, `command` is spawned directly.
+struct Exec;
+impl Shell for Exec {
+    fn name(&self) -> &'static str {
+        "exec"
+    }
+
+    fn build(&self, command: &str, args: &[String], working_dir: &Path) -> Command {
+        let mut cmd = Command::new(command);
+        cmd.args(args);
+        cmd.current_dir(working_dir);
+        cmd
+    }
+}
+
+fn join_command(command: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        command.to_string()
+    } else {
+        format!("{} {}", command, args.join(" "))
+    }
+}
+
+fn resolve_shell(name: &str) -> Result<Box<dyn Shell>, String> {
+    match name {
+        "powershell" => Ok(Box::new(PowerShell)),
+        "cmd" => Ok(Box::new(Cmd)),
+        "bash" => Ok(Box::new(Bash)),
+        "sh" => Ok(Box::new(Sh)),
+        "exec" => Ok(Box::new(Exec)),
+        other => Err(format!("Unknown shell backend: {}", other)),
+    }
+}
+
+fn default_shell_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "powershell"
+    } else {
+        "bash"
+    }
+}
+
+/// Configurable allowlist of shell backends this app will spawn, so the set of
+/// permitted shells isn't a single hard-coded string match.
+pub struct ShellAllowlist {
+    allowed: Mutex<HashSet<String>>,
+}
+
+impl ShellAllowlist {
+    pub fn new() -> Self {
+        let defaults: &[&str] = if cfg!(target_os = "windows") {
+            &["powershell", "cmd", "exec"]
+        } else {
+            &["bash", "sh", "exec"]
+        };
+
+        Self {
+            allowed: Mutex::new(defaults.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allowed
+            .lock()
+            .map(|set| set.contains(name))
+            .unwrap_or(false)
+    }
+
+    pub fn set_allowed(&self, names: Vec<String>) {
+        if let Ok(mut set) = self.allowed.lock() {
+            *set = names.into_iter().collect();
+        }
+    }
+
+    pub fn get_allowed(&self) -> Vec<String> {
+        self.allowed.lock().map(|set| set.iter().cloned().collect()).unwrap_or_default()
+    }
+}
+
+/// Tauri command so the frontend can show which shell backends are currently permitted.
+#[tauri::command]
+pub fn tool_shell_allowlist_get(shells: tauri::State<'_, Arc<ShellAllowlist>>) -> Result<Vec<String>, String> {
+    Ok(shells.get_allowed())
+}
+
+/// Tauri command to reconfigure the shell allowlist at runtime, mirroring `tool_policy_reload`.
+#[tauri::command]
+pub fn tool_shell_allowlist_set(
+    names: Vec<String>,
+    shells: tauri::State<'_, Arc<ShellAllowlist>>,
+) -> Result<Vec<String>, String> {
+    shells.set_allowed(names);
+    Ok(shells.get_allowed())
+}
+
+fn resolve_working_dir(working_dir: Option<&str>) -> Result<PathBuf, String> {
+    let raw = match working_dir {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::current_dir()
+            .map_err(|e| format!("Failed to resolve current directory: {}", e))?,
+    };
+
+    if let Ok(canonical) = raw.canonicalize() {
+        return Ok(canonical);
+    }
+
+    if raw.is_absolute() {
+        Ok(raw)
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(&raw))
+            .map_err(|e| format!("Failed to resolve working directory '{}': {}", raw.display(), e))
+    }
+}
+
+/// Run `child` to completion, killing it and returning `timed_out: true` if it
+/// outlives `timeout`. Output is drained on background threads so a full pipe buffer
+/// can't deadlock the timeout poll.
+fn run_with_timeout(
+    mut child: Child,
+    timeout: Option<Duration>,
+) -> Result<(Option<ExitStatus>, String, String, bool), String> {
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    let stdout_handle = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = stdout_pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    });
+    let stderr_handle = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = stderr_pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    });
+
+    let start = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {
+                if let Some(limit) = timeout {
+                    if start.elapsed() >= limit {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        timed_out = true;
+                        break None;
+                    }
+                }
+                std::thread::sleep(Duration::from_millis(25));
+            }
+            Err(e) => return Err(format!("Failed to poll child process: {}", e)),
+        }
+    };
+
+    let stdout_bytes = stdout_handle.join().unwrap_or_default();
+    let stderr_bytes = stderr_handle.join().unwrap_or_default();
+
+    Ok((
+        status,
+        String::from_utf8_lossy(&stdout_bytes).to_string(),
+        String::from_utf8_lossy(&stderr_bytes).to_string(),
+        timed_out,
+    ))
+}
 
 #[tauri::command]
 pub fn tool_run_command(
     command: String,
     args: Option<Vec<String>>,
     working_dir: Option<String>,
+    shell: Option<String>,
+    timeout_ms: Option<u64>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    shells: tauri::State<'_, Arc<ShellAllowlist>>,
 ) -> Result<serde_json::Value, String> {
-    // Validate command is PowerShell for security
-    if !command.to_lowercase().contains("powershell.exe") && !command.eq("powershell") {
-        return Err(format!("Only PowerShell commands are allowed for security reasons"));
+    let working_dir = resolve_working_dir(working_dir.as_deref())?;
+
+    policy
+        .check_path(Capability::CommandRun, &working_dir)
+        .map_err(|e| e.to_string())?;
+
+    let shell_name = shell
+        .unwrap_or_else(|| default_shell_name().to_string())
+        .to_lowercase();
+
+    if !shells.is_allowed(&shell_name) {
+        return Err(format!(
+            "Shell '{}' is not in the permitted allowlist",
+            shell_name
+        ));
     }
 
-    let mut cmd = Command::new("powershell.exe");
-    
-    // Build command string from args
-    let command_str = if let Some(arg_list) = args.as_ref() {
-        if arg_list.is_empty() {
-            String::new()
-        } else {
-            // Join all args into a single command string
-            arg_list.join(" ")
-        }
-    } else {
-        String::new()
-    };
-    
-    // Set UTF-8 encoding and execute command
-    cmd.args(&[
-        "-NoProfile",
-        "-NoLogo",
-        "-NonInteractive",
-        "-ExecutionPolicy", "Bypass",
-        "-Command",
-        &format!("[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; {}", command_str)
-    ]);
-
-    // Set working directory if provided
-    if let Some(dir) = working_dir {
-        cmd.current_dir(&dir);
-    }
-
-    // Execute command
-    let output = cmd.output()
+    let backend = resolve_shell(&shell_name)?;
+    let args = args.unwrap_or_default();
+
+    let mut cmd = backend.build(&command, &args, &working_dir);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let child = cmd
+        .spawn()
         .map_err(|e| format!("Failed to execute command: {}", e))?;
 
-    // Convert output to UTF-8 strings
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let exit_code = output.status.code().unwrap_or(-1);
+    let timeout = timeout_ms.map(Duration::from_millis);
+    let (exit_status, stdout, stderr, timed_out) = run_with_timeout(child, timeout)?;
 
-    // Return structured result
     Ok(serde_json::json!({
         "stdout": stdout,
         "stderr": stderr,
-        "exitCode": exit_code,
-        "success": output.status.success()
+        "exitCode": exit_status.and_then(|s| s.code()).unwrap_or(-1),
+        "success": exit_status.map(|s| s.success()).unwrap_or(false),
+        "timedOut": timed_out,
+        "shell": backend.name(),
     }))
 }
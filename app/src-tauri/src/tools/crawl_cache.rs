@@ -0,0 +1,159 @@
+/**
+ * Shared directory walking for `tool_list_directory` and `tool_search_files`.
+ *
+ * Walks with `ignore::WalkBuilder` so both commands honor `.gitignore`, `.ignore`,
+ * and global git excludes and skip hidden directories by default, instead of the
+ * hand-rolled recursion / plain `glob` that used to flood the AI context with
+ * `node_modules`, `.git`, and `target`.
+ */
+
+use ignore::WalkBuilder;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DirEntry {
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// Options shared by both commands for filtering a directory walk.
+pub struct WalkOptions {
+    pub recursive: bool,
+    pub respect_gitignore: bool,
+    pub include_hidden: bool,
+    pub max_depth: Option<usize>,
+    pub extensions: Option<Vec<String>>,
+}
+
+/// Walk `root` and return every matching entry as a path relative to it.
+pub fn walk_entries(root: &Path, options: &WalkOptions) -> Result<Vec<DirEntry>, String> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(!options.include_hidden)
+        .git_ignore(options.respect_gitignore)
+        .git_global(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore)
+        .ignore(options.respect_gitignore)
+        .parents(options.respect_gitignore);
+
+    if options.recursive {
+        if let Some(depth) = options.max_depth {
+            // +1 because the walker counts the root itself as depth 0.
+            builder.max_depth(Some(depth + 1));
+        }
+    } else {
+        builder.max_depth(Some(1));
+    }
+
+    let ext_filter: Option<HashSet<String>> = options.extensions.as_ref().map(|exts| {
+        exts.iter()
+            .map(|e| e.trim_start_matches('.').to_lowercase())
+            .collect()
+    });
+
+    let mut results = Vec::new();
+    for entry in builder.build() {
+        let entry = entry.map_err(|e| format!("Failed to walk directory: {}", e))?;
+        if entry.path() == root {
+            continue;
+        }
+
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+
+        if !is_dir {
+            if let Some(ref exts) = ext_filter {
+                let matches_ext = entry
+                    .path()
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .map(|s| exts.contains(&s.to_lowercase()))
+                    .unwrap_or(false);
+                if !matches_ext {
+                    continue;
+                }
+            }
+        }
+
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        results.push(DirEntry {
+            path: relative,
+            is_dir,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Caches the result of a walk so repeated calls with the same root/options during a
+/// session short-circuit instead of re-crawling the filesystem, mirroring how lsp-ai's
+/// crawler remembers which extensions it has already indexed for a directory.
+///
+/// Invalidated by every mutating file/directory tool (`tool_write_file`,
+/// `tool_delete_file`, `tool_move_file`, `tool_create_directory`) via `invalidate`, so
+/// a cached listing never outlives the change that made it stale.
+pub struct CrawlCache {
+    entries: Mutex<HashMap<String, (PathBuf, Vec<DirEntry>)>>,
+}
+
+impl CrawlCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get_or_walk(&self, root: &Path, options: &WalkOptions) -> Result<Vec<DirEntry>, String> {
+        let key = cache_key(root, options);
+
+        if let Some((_, cached)) = self.entries.lock().map_err(|e| e.to_string())?.get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let results = walk_entries(root, options)?;
+        self.entries
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(key, (root.to_path_buf(), results.clone()));
+
+        Ok(results)
+    }
+
+    /// Drop every cached walk whose root is an ancestor of (or equal to) `changed_path`,
+    /// e.g. after the agent creates, writes, deletes, or moves something under it. A
+    /// walk rooted above the change (recursive listings of a parent directory) would
+    /// otherwise keep returning the stale snapshot forever.
+    pub fn invalidate(&self, changed_path: &Path) {
+        if let Ok(mut map) = self.entries.lock() {
+            map.retain(|_, (root, _)| !changed_path.starts_with(root.as_path()));
+        }
+    }
+}
+
+fn cache_key(root: &Path, options: &WalkOptions) -> String {
+    let mut extensions = options
+        .extensions
+        .clone()
+        .unwrap_or_default()
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect::<Vec<_>>();
+    extensions.sort();
+
+    format!(
+        "{}|recursive={}|gitignore={}|hidden={}|depth={:?}|ext={}",
+        root.to_string_lossy().replace('\\', "/"),
+        options.recursive,
+        options.respect_gitignore,
+        options.include_hidden,
+        options.max_depth,
+        extensions.join(",")
+    )
+}
@@ -1,21 +1,35 @@
 /**
  * Diff Application Tools
- * 
+ *
  * Rust implementation for applying diffs to files:
- * - apply_diff: Apply unified diff format to a file
+ * - apply_diff: Apply unified diff format to a file using context-aware fuzzy matching
  */
 
+use crate::policy::{Capability, ToolPolicy};
+use similar::TextDiff;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
-#[allow(dead_code)]
-#[derive(serde::Deserialize)]
-pub struct DiffHunk {
+/// Minimum similarity ratio (0.0-1.0) a fuzzy candidate block must reach to be accepted
+/// when no exact context match exists.
+const SIMILARITY_THRESHOLD: f64 = 0.7;
+
+struct Hunk {
     old_start: usize,
-    old_count: usize,
-    new_start: usize,
-    new_count: usize,
-    lines: Vec<String>,
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct HunkReport {
+    pub header: String,
+    pub applied: bool,
+    /// How far (in lines) the matched block was from the hunk's declared position,
+    /// after adjusting that position for the line shift of earlier applied hunks.
+    /// 0 when no match was found.
+    pub offset: i64,
+    pub reason: Option<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -25,15 +39,21 @@ pub struct ApplyDiffResult {
     lines_changed: usize,
     lines_added: usize,
     lines_removed: usize,
+    hunks: Vec<HunkReport>,
 }
 
 #[tauri::command]
 pub fn tool_apply_diff(
     path: String,
     diff_content: String,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
 ) -> Result<ApplyDiffResult, String> {
+    policy
+        .check_path(Capability::FsWrite, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
     let file_path = Path::new(&path);
-    
+
     // Check if file exists
     if !file_path.exists() {
         return Err(format!("File does not exist: {}", path));
@@ -42,68 +62,244 @@ pub fn tool_apply_diff(
     // Read original file content
     let original_content = fs::read_to_string(&path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
-    
-    let mut original_lines: Vec<String> = original_content.lines().map(|s| s.to_string()).collect();
 
-    // Parse diff content (simplified unified diff format)
-    let diff_lines: Vec<&str> = diff_content.lines().collect();
-    
+    // Preserve the file's original line-ending style and trailing-newline state
+    let uses_crlf = original_content.contains("\r\n");
+    let had_trailing_newline = original_content.ends_with('\n');
+    let normalized = original_content.replace("\r\n", "\n");
+    let mut lines: Vec<String> = normalized.lines().map(|s| s.to_string()).collect();
+
+    let hunks = parse_hunks(&diff_content);
+
     let mut lines_added = 0;
     let mut lines_removed = 0;
-    let mut current_line = 0;
+    let mut hunk_reports = Vec::with_capacity(hunks.len());
+    // Cumulative line-count shift from previously applied hunks, so later hunks in the
+    // same diff still search near their true current position.
+    let mut cumulative_shift: i64 = 0;
 
-    let mut i = 0;
-    while i < diff_lines.len() {
-        let line = diff_lines[i];
-        
-        // Parse hunk header (e.g., @@ -1,3 +1,4 @@)
-        if line.starts_with("@@") {
-            if let Some(hunk_info) = parse_hunk_header(line) {
-                current_line = hunk_info.0 - 1; // Convert to 0-based index
-            }
-            i += 1;
-            continue;
-        }
+    for hunk in &hunks {
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            hunk.old_start,
+            hunk.old_lines.len(),
+            hunk.old_start,
+            hunk.new_lines.len()
+        );
 
-        // Process diff lines
-        if line.starts_with("-") && !line.starts_with("---") {
-            // Remove line
-            if current_line < original_lines.len() {
-                original_lines.remove(current_line);
-                lines_removed += 1;
+        let expected_start = if hunk.old_start == 0 {
+            0
+        } else if hunk.old_lines.is_empty() {
+            // Pure-insertion hunk (old count 0, e.g. `@@ -3,0 +4 @@`): unified-diff
+            // convention is "insert after old line N", which is splice point N itself,
+            // not N - 1 (that's only the 1-indexed-to-0-indexed conversion for a hunk
+            // that actually has an old line at N).
+            (hunk.old_start as i64 + cumulative_shift).max(0) as usize
+        } else {
+            (hunk.old_start as i64 - 1 + cumulative_shift).max(0) as usize
+        };
+
+        match locate_hunk(&lines, &hunk.old_lines, expected_start) {
+            Some((offset, reason)) => {
+                let end = offset + hunk.old_lines.len();
+                if end > lines.len() {
+                    hunk_reports.push(HunkReport {
+                        header,
+                        applied: false,
+                        offset: offset as i64 - expected_start as i64,
+                        reason: Some("Matched block extends beyond end of file".to_string()),
+                    });
+                    continue;
+                }
+
+                lines.splice(offset..end, hunk.new_lines.iter().cloned());
+                lines_removed += hunk.old_lines.len();
+                lines_added += hunk.new_lines.len();
+                cumulative_shift += hunk.new_lines.len() as i64 - hunk.old_lines.len() as i64;
+
+                hunk_reports.push(HunkReport {
+                    header,
+                    applied: true,
+                    offset: offset as i64 - expected_start as i64,
+                    reason,
+                });
+            }
+            None => {
+                hunk_reports.push(HunkReport {
+                    header,
+                    applied: false,
+                    offset: 0,
+                    reason: Some(format!(
+                        "No context match found within similarity threshold ({:.0}%)",
+                        SIMILARITY_THRESHOLD * 100.0
+                    )),
+                });
             }
-        } else if line.starts_with("+") && !line.starts_with("+++") {
-            // Add line
-            let new_line = line[1..].to_string();
-            original_lines.insert(current_line, new_line);
-            lines_added += 1;
-            current_line += 1;
-        } else if line.starts_with(" ") {
-            // Context line (no change)
-            current_line += 1;
-        } else if line.starts_with("---") || line.starts_with("+++") {
-            // File headers, skip
         }
-        
-        i += 1;
     }
 
-    // Write modified content back to file
-    let new_content = original_lines.join("\n");
-    fs::write(&path, new_content)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    let applied_count = hunk_reports.iter().filter(|h| h.applied).count();
+    let success = !hunks.is_empty() && applied_count == hunks.len();
 
-    let lines_changed = lines_added + lines_removed;
+    if applied_count > 0 {
+        let mut new_content = lines.join("\n");
+        if had_trailing_newline {
+            new_content.push('\n');
+        }
+        if uses_crlf {
+            new_content = new_content.replace('\n', "\r\n");
+        }
+        fs::write(&path, new_content)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+
+    let message = if hunks.is_empty() {
+        format!("No hunks found in diff for {}", path)
+    } else if success {
+        format!("Successfully applied {} hunk(s) to {}", applied_count, path)
+    } else {
+        format!(
+            "Applied {}/{} hunk(s) to {} ({} rejected)",
+            applied_count,
+            hunks.len(),
+            path,
+            hunks.len() - applied_count
+        )
+    };
 
     Ok(ApplyDiffResult {
-        success: true,
-        message: format!("Successfully applied diff to {}", path),
-        lines_changed,
+        success,
+        message,
+        lines_changed: lines_added + lines_removed,
         lines_added,
         lines_removed,
+        hunks: hunk_reports,
     })
 }
 
+/// Find the hunk's old block (context + removed lines) in the current file.
+///
+/// Searches outward from `expected_start` (nearest offset first) for an exact match.
+/// If nothing matches exactly, falls back to the candidate window with the highest
+/// `similar::TextDiff` ratio, accepting it only if it clears `SIMILARITY_THRESHOLD`.
+/// Returns the matched start index plus an optional note when a fuzzy match was used.
+fn locate_hunk(
+    lines: &[String],
+    old_block: &[String],
+    expected_start: usize,
+) -> Option<(usize, Option<String>)> {
+    if old_block.is_empty() {
+        return Some((expected_start.min(lines.len()), None));
+    }
+
+    if old_block.len() <= lines.len() {
+        let max_offset = lines.len() - old_block.len();
+        let mut distance = 0usize;
+        loop {
+            let mut candidates = Vec::with_capacity(2);
+            if let Some(c) = expected_start.checked_sub(distance) {
+                candidates.push(c);
+            }
+            if distance > 0 {
+                let up = expected_start + distance;
+                if up <= max_offset {
+                    candidates.push(up);
+                }
+            } else if expected_start <= max_offset {
+                candidates.push(expected_start);
+            }
+
+            for candidate in candidates {
+                if candidate > max_offset {
+                    continue;
+                }
+                if lines[candidate..candidate + old_block.len()] == *old_block {
+                    return Some((candidate, None));
+                }
+            }
+
+            if expected_start.saturating_sub(distance) == 0 && expected_start + distance >= max_offset {
+                break;
+            }
+            distance += 1;
+        }
+    }
+
+    best_fuzzy_match(lines, old_block)
+}
+
+/// Fall back to similarity-ratio matching over every candidate window the same size as
+/// `old_block`, returning the best one if it clears `SIMILARITY_THRESHOLD`.
+fn best_fuzzy_match(lines: &[String], old_block: &[String]) -> Option<(usize, Option<String>)> {
+    let window_size = old_block.len();
+    if window_size == 0 || window_size > lines.len() {
+        return None;
+    }
+
+    let old_text = old_block.join("\n");
+    let mut best: Option<(usize, f64)> = None;
+
+    for start in 0..=(lines.len() - window_size) {
+        let candidate_text = lines[start..start + window_size].join("\n");
+        let ratio = TextDiff::from_lines(&old_text, &candidate_text).ratio() as f64;
+        if best.map_or(true, |(_, best_ratio)| ratio > best_ratio) {
+            best = Some((start, ratio));
+        }
+    }
+
+    best.filter(|(_, ratio)| *ratio >= SIMILARITY_THRESHOLD)
+        .map(|(start, ratio)| {
+            (
+                start,
+                Some(format!("Fuzzy-matched at {:.0}% similarity", ratio * 100.0)),
+            )
+        })
+}
+
+/// Parse a unified diff body into per-hunk old/new line blocks.
+fn parse_hunks(diff_content: &str) -> Vec<Hunk> {
+    let diff_lines: Vec<&str> = diff_content.lines().collect();
+    let mut hunks = Vec::new();
+
+    let mut i = 0;
+    while i < diff_lines.len() {
+        let line = diff_lines[i];
+        if line.starts_with("@@") {
+            if let Some((old_start, _, _, _)) = parse_hunk_header(line) {
+                let mut old_lines = Vec::new();
+                let mut new_lines = Vec::new();
+                i += 1;
+                while i < diff_lines.len() && !diff_lines[i].starts_with("@@") {
+                    let hline = diff_lines[i];
+                    if hline.starts_with("---") || hline.starts_with("+++") {
+                        // File header lines some diff dialects interleave between hunks
+                    } else if let Some(rest) = hline.strip_prefix('-') {
+                        old_lines.push(rest.to_string());
+                    } else if let Some(rest) = hline.strip_prefix('+') {
+                        new_lines.push(rest.to_string());
+                    } else if let Some(rest) = hline.strip_prefix(' ') {
+                        old_lines.push(rest.to_string());
+                        new_lines.push(rest.to_string());
+                    } else if hline.is_empty() {
+                        old_lines.push(String::new());
+                        new_lines.push(String::new());
+                    }
+                    i += 1;
+                }
+                hunks.push(Hunk {
+                    old_start,
+                    old_lines,
+                    new_lines,
+                });
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    hunks
+}
+
 // Parse unified diff hunk header
 // Format: @@ -old_start,old_count +new_start,new_count @@
 fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize, usize)> {
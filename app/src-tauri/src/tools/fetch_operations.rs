@@ -5,8 +5,20 @@
  * - fetch: Fetch content from URLs with timeout and headers support
  */
 
+use crate::policy::ToolPolicy;
+use crate::tools::gemini_protocol;
+use base64::Engine as _;
 use serde_json::json;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tauri::Manager;
+
+#[derive(serde::Serialize, Clone)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+}
 
 #[tauri::command]
 pub fn tool_fetch(
@@ -14,24 +26,76 @@ pub fn tool_fetch(
     method: Option<String>,
     headers: Option<std::collections::HashMap<String, String>>,
     timeout: Option<u64>,
+    body: Option<serde_json::Value>,
+    body_type: Option<String>,
+    max_bytes: Option<u64>,
+    response_encoding: Option<String>,
+    follow_redirects: Option<bool>,
+    max_redirects: Option<usize>,
+    retries: Option<u32>,
+    proxy: Option<String>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    app: tauri::AppHandle,
 ) -> Result<serde_json::Value, String> {
     // Validate URL
     if url.is_empty() {
         return Err("URL cannot be empty".to_string());
     }
 
-    // Only allow HTTP/HTTPS protocols for security
+    if url.starts_with("gemini://") {
+        return fetch_gemini(&url, timeout, &policy, &app);
+    }
+
+    // Only allow HTTP/HTTPS/Gemini protocols for security
     if !url.starts_with("http://") && !url.starts_with("https://") {
-        return Err("Only HTTP and HTTPS protocols are allowed".to_string());
+        return Err("Only HTTP, HTTPS, and Gemini protocols are allowed".to_string());
     }
 
+    policy.check_host(&url).map_err(|e| e.to_string())?;
+
     let method_str = method.unwrap_or_else(|| "GET".to_string()).to_uppercase();
     let timeout_secs = timeout.unwrap_or(30);
 
+    // Drive redirects through a custom policy instead of reqwest's opaque default so
+    // we can record each hop; `follow_redirects: false` stops at the first response
+    // (even a 3xx) so callers can inspect a redirect directly.
+    let follow_redirects = follow_redirects.unwrap_or(true);
+    let max_redirects = max_redirects.unwrap_or(10);
+    let redirect_chain: Arc<Mutex<Vec<RedirectHop>>> = Arc::new(Mutex::new(Vec::new()));
+    let chain_for_policy = redirect_chain.clone();
+
+    let redirect_policy = if !follow_redirects {
+        reqwest::redirect::Policy::none()
+    } else {
+        reqwest::redirect::Policy::custom(move |attempt| {
+            // `previous()` includes the original URL, so it holds one more entry than
+            // the number of redirects followed so far.
+            if attempt.previous().len() > max_redirects {
+                return attempt.stop();
+            }
+            if let Some(last_url) = attempt.previous().last() {
+                chain_for_policy.lock().unwrap().push(RedirectHop {
+                    url: last_url.to_string(),
+                    status: attempt.status().as_u16(),
+                });
+            }
+            attempt.follow()
+        })
+    };
+
     // Build HTTP client with timeout
-    let client = reqwest::blocking::Client::builder()
+    let mut client_builder = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(timeout_secs))
         .user_agent("GeminiGUI/0.1.0")
+        .redirect(redirect_policy);
+
+    if let Some(proxy_url) = &proxy {
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?;
+        client_builder = client_builder.proxy(proxy);
+    }
+
+    let client = client_builder
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
@@ -46,23 +110,53 @@ pub fn tool_fetch(
     };
 
     // Add custom headers if provided
+    let mut has_content_type_header = false;
     if let Some(header_map) = headers {
         for (key, value) in header_map {
+            if key.eq_ignore_ascii_case("content-type") {
+                has_content_type_header = true;
+            }
             request_builder = request_builder.header(key, value);
         }
     }
 
-    // Execute request
+    // Attach a request body if one was provided
+    if let Some(body_value) = body {
+        request_builder = attach_body(request_builder, body_value, body_type, has_content_type_header)?;
+    }
+
+    // Execute request, retrying transient failures (connection errors, 429, 5xx) with
+    // exponential backoff; a `Retry-After` header on the response takes priority over
+    // the computed backoff delay.
+    let max_attempts = retries.unwrap_or(0);
     let start_time = std::time::Instant::now();
-    let response = request_builder
-        .send()
-        .map_err(|e| format!("Failed to send HTTP request: {}", e))?;
+    let mut attempt = 0u32;
+    let response = loop {
+        let attempt_builder = request_builder
+            .try_clone()
+            .ok_or_else(|| "Request body does not support retries".to_string())?;
+
+        match attempt_builder.send() {
+            Ok(resp) if attempt < max_attempts && is_retryable_status(resp.status()) => {
+                std::thread::sleep(retry_delay(&resp, attempt));
+                attempt += 1;
+            }
+            Ok(resp) => break resp,
+            Err(_) if attempt < max_attempts => {
+                std::thread::sleep(backoff_delay(attempt));
+                attempt += 1;
+            }
+            Err(e) => return Err(format!("Failed to send HTTP request: {}", e)),
+        }
+    };
 
     let elapsed_ms = start_time.elapsed().as_millis() as u64;
 
     // Extract response data
     let status = response.status().as_u16();
     let success = response.status().is_success();
+    let final_url = response.url().to_string();
+    let redirect_chain: Vec<RedirectHop> = redirect_chain.lock().unwrap().clone();
     let headers_map: std::collections::HashMap<String, String> = response
         .headers()
         .iter()
@@ -74,20 +168,356 @@ pub fn tool_fetch(
         .cloned()
         .unwrap_or_else(|| "text/plain".to_string());
 
-    // Get response body as text
-    let body = response
-        .text()
+    // Stream the body while counting bytes so a huge download can't exhaust memory;
+    // default cap matches the common "don't blindly download more than 10 MB" guard.
+    let max_bytes = max_bytes.unwrap_or(10 * 1024 * 1024);
+    let mut body_bytes = Vec::new();
+    response
+        .take(max_bytes + 1)
+        .read_to_end(&mut body_bytes)
         .map_err(|e| format!("Failed to read response body: {}", e))?;
 
+    if body_bytes.len() as u64 > max_bytes {
+        return Err(format!(
+            "Response body exceeded maxBytes limit of {} bytes",
+            max_bytes
+        ));
+    }
+
+    let force_base64 = response_encoding
+        .as_deref()
+        .map(|enc| enc.eq_ignore_ascii_case("base64"))
+        .unwrap_or(false);
+    let is_text_content_type = is_text_content_type(&content_type);
+    let is_binary = force_base64 || !is_text_content_type;
+
     // Return structured result
+    if is_binary {
+        let body_base64 = base64::engine::general_purpose::STANDARD.encode(&body_bytes);
+        Ok(json!({
+            "success": success,
+            "status": status,
+            "headers": headers_map,
+            "contentType": content_type,
+            "bodyBase64": body_base64,
+            "bodyLength": body_bytes.len(),
+            "isBinary": true,
+            "elapsedMs": elapsed_ms,
+            "url": url,
+            "finalUrl": final_url,
+            "redirectChain": redirect_chain,
+        }))
+    } else {
+        let body = String::from_utf8_lossy(&body_bytes).to_string();
+        Ok(json!({
+            "success": success,
+            "status": status,
+            "headers": headers_map,
+            "contentType": content_type,
+            "body": body,
+            "bodyLength": body.len(),
+            "isBinary": false,
+            "elapsedMs": elapsed_ms,
+            "url": url,
+            "finalUrl": final_url,
+            "redirectChain": redirect_chain,
+        }))
+    }
+}
+
+/// Like `tool_fetch`, but streams the body in chunks and reports progress over an
+/// IPC channel instead of returning the whole response in one shot — useful for large
+/// downloads where the frontend wants a progress bar rather than a single blocking call.
+/// Redirects always follow reqwest's default policy here, and `bodyBase64`/`body` are
+/// only known once the final `"done"` event fires since encoding depends on content type.
+#[tauri::command]
+pub fn tool_fetch_stream(
+    url: String,
+    method: Option<String>,
+    headers: Option<std::collections::HashMap<String, String>>,
+    timeout: Option<u64>,
+    body: Option<serde_json::Value>,
+    body_type: Option<String>,
+    chunk_size: Option<usize>,
+    max_bytes: Option<u64>,
+    channel: tauri::ipc::Channel<serde_json::Value>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("URL cannot be empty".to_string());
+    }
+
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("tool_fetch_stream only supports HTTP and HTTPS URLs".to_string());
+    }
+
+    policy.check_host(&url).map_err(|e| e.to_string())?;
+
+    let method_str = method.unwrap_or_else(|| "GET".to_string()).to_uppercase();
+    let timeout_secs = timeout.unwrap_or(30);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .user_agent("GeminiGUI/0.1.0")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut request_builder = match method_str.as_str() {
+        "GET" => client.get(&url),
+        "POST" => client.post(&url),
+        "PUT" => client.put(&url),
+        "DELETE" => client.delete(&url),
+        "HEAD" => client.head(&url),
+        _ => return Err(format!("Unsupported HTTP method: {}", method_str)),
+    };
+
+    let mut has_content_type_header = false;
+    if let Some(header_map) = headers {
+        for (key, value) in header_map {
+            if key.eq_ignore_ascii_case("content-type") {
+                has_content_type_header = true;
+            }
+            request_builder = request_builder.header(key, value);
+        }
+    }
+
+    if let Some(body_value) = body {
+        request_builder = attach_body(request_builder, body_value, body_type, has_content_type_header)?;
+    }
+
+    let start_time = std::time::Instant::now();
+    let mut response = request_builder
+        .send()
+        .map_err(|e| format!("Failed to send HTTP request: {}", e))?;
+
+    let status = response.status().as_u16();
+    let success = response.status().is_success();
+    let final_url = response.url().to_string();
+    let headers_map: std::collections::HashMap<String, String> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let content_type = headers_map
+        .get("content-type")
+        .cloned()
+        .unwrap_or_else(|| "text/plain".to_string());
+    let total_bytes = response.content_length();
+
+    // Same default cap as `tool_fetch`: stream into memory chunk by chunk, but still bail
+    // out once the accumulated body would exceed it instead of absorbing an unbounded
+    // download.
+    let max_bytes = max_bytes.unwrap_or(10 * 1024 * 1024);
+    let chunk_size = chunk_size.unwrap_or(64 * 1024).max(1);
+    let mut buf = vec![0u8; chunk_size];
+    let mut body_bytes = Vec::new();
+    let mut bytes_received: u64 = 0;
+
+    loop {
+        let read = response
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read response chunk: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        bytes_received += read as u64;
+
+        if bytes_received > max_bytes {
+            let message = format!("Response body exceeded maxBytes limit of {} bytes", max_bytes);
+            channel
+                .send(json!({
+                    "event": "error",
+                    "message": message,
+                    "bytesReceived": bytes_received,
+                    "elapsedMs": start_time.elapsed().as_millis() as u64,
+                }))
+                .map_err(|e| format!("Failed to send error event: {}", e))?;
+            return Err(message);
+        }
+
+        body_bytes.extend_from_slice(&buf[..read]);
+
+        channel
+            .send(json!({
+                "event": "progress",
+                "bytesReceived": bytes_received,
+                "totalBytes": total_bytes,
+                "elapsedMs": start_time.elapsed().as_millis() as u64,
+            }))
+            .map_err(|e| format!("Failed to send progress event: {}", e))?;
+    }
+
+    let elapsed_ms = start_time.elapsed().as_millis() as u64;
+    let is_binary = !is_text_content_type(&content_type);
+
+    let done_event = if is_binary {
+        let body_base64 = base64::engine::general_purpose::STANDARD.encode(&body_bytes);
+        json!({
+            "event": "done",
+            "success": success,
+            "status": status,
+            "headers": headers_map,
+            "contentType": content_type,
+            "bodyBase64": body_base64,
+            "bodyLength": body_bytes.len(),
+            "isBinary": true,
+            "elapsedMs": elapsed_ms,
+            "url": url,
+            "finalUrl": final_url,
+        })
+    } else {
+        let body = String::from_utf8_lossy(&body_bytes).to_string();
+        json!({
+            "event": "done",
+            "success": success,
+            "status": status,
+            "headers": headers_map,
+            "contentType": content_type,
+            "body": body,
+            "bodyLength": body.len(),
+            "isBinary": false,
+            "elapsedMs": elapsed_ms,
+            "url": url,
+            "finalUrl": final_url,
+        })
+    };
+
+    channel
+        .send(done_event)
+        .map_err(|e| format!("Failed to send completion event: {}", e))
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.as_u16() == 429 || status.is_server_error()
+}
+
+/// Honor a numeric `Retry-After` header (in seconds) when present, otherwise fall
+/// back to the computed exponential backoff for this attempt.
+fn retry_delay(response: &reqwest::blocking::Response, attempt: u32) -> Duration {
+    let retry_after_secs = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok());
+
+    match retry_after_secs {
+        Some(secs) => Duration::from_secs(secs),
+        None => backoff_delay(attempt),
+    }
+}
+
+/// Exponential backoff (250ms base, 10s cap) with up to 50% jitter so concurrent
+/// retries against the same host don't all land on the same tick.
+fn backoff_delay(attempt: u32) -> Duration {
+    let base_ms: u64 = 250;
+    let cap_ms: u64 = 10_000;
+    let exp_ms = base_ms.saturating_mul(1u64 << attempt.min(20)).min(cap_ms);
+    let jitter_ms = rand::random::<u64>() % (exp_ms / 2 + 1);
+    Duration::from_millis(exp_ms / 2 + jitter_ms)
+}
+
+fn is_text_content_type(content_type: &str) -> bool {
+    let content_type = content_type.to_lowercase();
+    content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+        || content_type.contains("javascript")
+        || content_type.contains("charset")
+}
+
+/// Attach `body` to the request per `body_type` ("json" | "text" | "form" | "base64"),
+/// defaulting to "json" so existing callers that just pass an object keep working.
+fn attach_body(
+    request_builder: reqwest::blocking::RequestBuilder,
+    body_value: serde_json::Value,
+    body_type: Option<String>,
+    has_content_type_header: bool,
+) -> Result<reqwest::blocking::RequestBuilder, String> {
+    let body_type = body_type.unwrap_or_else(|| "json".to_string()).to_lowercase();
+
+    match body_type.as_str() {
+        "json" => {
+            let builder = if has_content_type_header {
+                request_builder
+            } else {
+                request_builder.header("Content-Type", "application/json")
+            };
+            let serialized = serde_json::to_string(&body_value)
+                .map_err(|e| format!("Failed to serialize JSON body: {}", e))?;
+            Ok(builder.body(serialized))
+        }
+        "text" => {
+            let text = body_value
+                .as_str()
+                .ok_or_else(|| "body must be a string when bodyType is 'text'".to_string())?
+                .to_string();
+            Ok(request_builder.body(text))
+        }
+        "form" => {
+            let object = body_value
+                .as_object()
+                .ok_or_else(|| "body must be an object when bodyType is 'form'".to_string())?;
+            let form_pairs: Vec<(String, String)> = object
+                .iter()
+                .map(|(key, value)| (key.clone(), json_value_to_form_string(value)))
+                .collect();
+            Ok(request_builder.form(&form_pairs))
+        }
+        "base64" => {
+            let encoded = body_value
+                .as_str()
+                .ok_or_else(|| "body must be a base64-encoded string when bodyType is 'base64'".to_string())?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Invalid base64 body: {}", e))?;
+            Ok(request_builder.body(bytes))
+        }
+        other => Err(format!("Unsupported bodyType: {}", other)),
+    }
+}
+
+fn json_value_to_form_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Handle a `gemini://` URL: TLS + TOFU pinning + the line-oriented Gemini exchange,
+/// shaped into the core JSON result fields `tool_fetch` returns for HTTP (`success`,
+/// `status`, `contentType`, `body`, `bodyLength`, `elapsedMs`, `url`) plus `redirect`
+/// for 3x statuses; there are no `headers`, `finalUrl`, or `redirectChain` fields.
+fn fetch_gemini(
+    url: &str,
+    timeout: Option<u64>,
+    policy: &ToolPolicy,
+    app: &tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    policy.check_host(url).map_err(|e| e.to_string())?;
+
+    let known_hosts_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("gemini_known_hosts");
+
+    let response = gemini_protocol::fetch_gemini(
+        url,
+        &known_hosts_path,
+        Duration::from_secs(timeout.unwrap_or(30)),
+    )?;
+
+    let success = (20..30).contains(&response.status);
+    let is_redirect = (30..40).contains(&response.status);
+
     Ok(json!({
         "success": success,
-        "status": status,
-        "headers": headers_map,
-        "contentType": content_type,
-        "body": body,
-        "bodyLength": body.len(),
-        "elapsedMs": elapsed_ms,
+        "status": response.status,
+        "contentType": response.meta.clone(),
+        "redirect": if is_redirect { Some(response.meta.clone()) } else { None },
+        "body": response.body,
+        "bodyLength": response.body.len(),
+        "elapsedMs": response.elapsed_ms,
         "url": url,
     }))
 }
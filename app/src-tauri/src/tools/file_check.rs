@@ -1,18 +1,41 @@
 /**
  * File Checking Tools
- * 
+ *
  * Rust implementation for file validation:
  * - file_check: Check file for syntax errors, linting issues, etc.
+ *
+ * Diagnostics are parser-backed rather than substring sniffing: `.rs` files go
+ * through `syn`, JSON/TOML errors are mapped to line/column, and JS/TS brace
+ * balancing runs through a small lexer that skips strings, template literals,
+ * regex literals, and comments instead of counting raw `{`/`}` characters.
  */
 
 use std::fs;
 use std::path::Path;
 
+#[derive(serde::Serialize, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub severity: String,
+}
+
+impl Diagnostic {
+    fn error(message: String, line: usize, column: usize) -> Self {
+        Self { message, line, column, severity: "error".to_string() }
+    }
+
+    fn warning(message: String, line: usize, column: usize) -> Self {
+        Self { message, line, column, severity: "warning".to_string() }
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct FileCheckResult {
     valid: bool,
-    errors: Vec<String>,
-    warnings: Vec<String>,
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
     file_type: String,
     line_count: usize,
     encoding: String,
@@ -21,7 +44,7 @@ pub struct FileCheckResult {
 #[tauri::command]
 pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
     let file_path = Path::new(&path);
-    
+
     // Check if file exists
     if !file_path.exists() {
         return Err(format!("File does not exist: {}", path));
@@ -55,7 +78,7 @@ pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("unknown");
-    
+
     let file_type = match extension {
         "rs" => "Rust",
         "ts" | "tsx" => "TypeScript",
@@ -70,33 +93,48 @@ pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
 
-    // Basic validation based on file type
+    // Parser-backed validation based on file type
     match extension {
+        "rs" => {
+            if let Err(e) = syn::parse_file(&text) {
+                let start = e.span().start();
+                errors.push(Diagnostic::error(e.to_string(), start.line, start.column + 1));
+            }
+        }
         "json" => {
-            // Check JSON syntax
             if let Err(e) = serde_json::from_str::<serde_json::Value>(&text) {
-                errors.push(format!("Invalid JSON syntax: {}", e));
+                errors.push(Diagnostic::error(
+                    format!("Invalid JSON syntax: {}", e),
+                    e.line().max(1),
+                    e.column().max(1),
+                ));
             }
         }
         "toml" => {
-            // Check TOML syntax
             if let Err(e) = toml::from_str::<toml::Value>(&text) {
-                errors.push(format!("Invalid TOML syntax: {}", e));
+                let (line, column) = toml_error_position(&e.to_string());
+                errors.push(Diagnostic::error(format!("Invalid TOML syntax: {}", e), line, column));
             }
         }
         "ts" | "tsx" | "js" | "jsx" => {
-            // Check for common issues
-            if text.contains("console.log") {
-                warnings.push("Found console.log statement".to_string());
-            }
-            if text.contains("debugger") {
-                warnings.push("Found debugger statement".to_string());
+            for diagnostic in check_js_ts_braces(&text) {
+                errors.push(diagnostic);
             }
-            // Check for unbalanced braces (simple check)
-            let open_braces = text.matches('{').count();
-            let close_braces = text.matches('}').count();
-            if open_braces != close_braces {
-                errors.push(format!("Unbalanced braces: {} open, {} close", open_braces, close_braces));
+            for (idx, line) in lines.iter().enumerate() {
+                if line.contains("console.log") {
+                    warnings.push(Diagnostic::warning(
+                        "Found console.log statement".to_string(),
+                        idx + 1,
+                        line.find("console.log").unwrap_or(0) + 1,
+                    ));
+                }
+                if line.contains("debugger") {
+                    warnings.push(Diagnostic::warning(
+                        "Found debugger statement".to_string(),
+                        idx + 1,
+                        line.find("debugger").unwrap_or(0) + 1,
+                    ));
+                }
             }
         }
         _ => {}
@@ -109,9 +147,16 @@ pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
         .filter(|(_, line)| line.ends_with(' ') || line.ends_with('\t'))
         .map(|(i, _)| i + 1)
         .collect();
-    
+
     if !trailing_whitespace_lines.is_empty() && trailing_whitespace_lines.len() < 10 {
-        warnings.push(format!("Trailing whitespace on lines: {:?}", trailing_whitespace_lines));
+        for line_number in &trailing_whitespace_lines {
+            let line_len = lines[line_number - 1].len();
+            warnings.push(Diagnostic::warning(
+                "Trailing whitespace".to_string(),
+                *line_number,
+                line_len,
+            ));
+        }
     }
 
     // Check for very long lines
@@ -121,9 +166,15 @@ pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
         .filter(|(_, line)| line.len() > 120)
         .map(|(i, _)| i + 1)
         .collect();
-    
+
     if !long_lines.is_empty() && long_lines.len() < 10 {
-        warnings.push(format!("Lines longer than 120 characters: {:?}", long_lines));
+        for line_number in &long_lines {
+            warnings.push(Diagnostic::warning(
+                format!("Line longer than 120 characters ({} chars)", lines[line_number - 1].len()),
+                *line_number,
+                121,
+            ));
+        }
     }
 
     Ok(FileCheckResult {
@@ -136,7 +187,237 @@ pub fn tool_file_check(path: String) -> Result<FileCheckResult, String> {
     })
 }
 
+/// Extract `(line, column)` from a toml crate error's Display text (e.g. "TOML parse
+/// error at line 3, column 10"), falling back to the start of the file if the message
+/// doesn't carry a position.
+fn toml_error_position(message: &str) -> (usize, usize) {
+    let re = regex::Regex::new(r"line (\d+), column (\d+)").unwrap();
+    if let Some(caps) = re.captures(message) {
+        let line = caps.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(1);
+        let column = caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(1);
+        (line, column)
+    } else {
+        (1, 1)
+    }
+}
+
+/// Balance `{}/()/[]` in JS/TS source while skipping strings, template literals
+/// (including nested `${ ... }` substitutions), (line and block) comments, and regex
+/// literals, so braces inside those don't throw off the count like the old
+/// substring-based check did. A string/template literal that never finds its closing
+/// quote before EOF is reported as an "Unterminated ..." error.
+fn check_js_ts_braces(text: &str) -> Vec<Diagnostic> {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    let mut i = 0usize;
+    let mut line = 1usize;
+    let mut column = 1usize;
+    let mut stack: Vec<(char, usize, usize)> = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut prev_significant: Option<char> = None;
+
+    fn advance(i: &mut usize, line: &mut usize, column: &mut usize, chars: &[char]) {
+        if chars[*i] == '\n' {
+            *line += 1;
+            *column = 1;
+        } else {
+            *column += 1;
+        }
+        *i += 1;
+    }
+
+    fn regex_allowed(prev: Option<char>) -> bool {
+        match prev {
+            None => true,
+            Some(c) => matches!(
+                c,
+                '(' | '[' | '{' | ',' | ';' | ':' | '!' | '&' | '|' | '?' | '=' | '+' | '-' | '*' | '/' | '%' | '~' | '^' | '<' | '>'
+            ),
+        }
+    }
+
+    while i < n {
+        let c = chars[i];
+
+        // Line comment
+        if c == '/' && i + 1 < n && chars[i + 1] == '/' {
+            while i < n && chars[i] != '\n' {
+                advance(&mut i, &mut line, &mut column, &chars);
+            }
+            continue;
+        }
+
+        // Block comment
+        if c == '/' && i + 1 < n && chars[i + 1] == '*' {
+            advance(&mut i, &mut line, &mut column, &chars);
+            advance(&mut i, &mut line, &mut column, &chars);
+            while i < n && !(chars[i] == '*' && i + 1 < n && chars[i + 1] == '/') {
+                advance(&mut i, &mut line, &mut column, &chars);
+            }
+            if i < n {
+                advance(&mut i, &mut line, &mut column, &chars);
+                advance(&mut i, &mut line, &mut column, &chars);
+            }
+            continue;
+        }
+
+        // String / template literal
+        if c == '\'' || c == '"' || c == '`' {
+            let quote = c;
+            let (start_line, start_col) = (line, column);
+            advance(&mut i, &mut line, &mut column, &chars);
+
+            // Depth of `${ ... }` substitutions currently open (template literals
+            // only), so a nested template/string inside one doesn't get mistaken for
+            // the outer literal's closing quote.
+            let mut template_depth = 0usize;
+            let mut terminated = false;
+
+            while i < n {
+                if chars[i] == '\\' && i + 1 < n {
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    continue;
+                }
+
+                if quote == '`' && template_depth == 0 && chars[i] == '$' && i + 1 < n && chars[i + 1] == '{' {
+                    template_depth += 1;
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    continue;
+                }
+
+                if template_depth > 0 {
+                    match chars[i] {
+                        '{' => {
+                            template_depth += 1;
+                            advance(&mut i, &mut line, &mut column, &chars);
+                        }
+                        '}' => {
+                            template_depth -= 1;
+                            advance(&mut i, &mut line, &mut column, &chars);
+                        }
+                        '\'' | '"' | '`' => {
+                            let nested_quote = chars[i];
+                            advance(&mut i, &mut line, &mut column, &chars);
+                            while i < n && chars[i] != nested_quote {
+                                if chars[i] == '\\' && i + 1 < n {
+                                    advance(&mut i, &mut line, &mut column, &chars);
+                                }
+                                advance(&mut i, &mut line, &mut column, &chars);
+                            }
+                            if i < n {
+                                advance(&mut i, &mut line, &mut column, &chars);
+                            }
+                        }
+                        _ => advance(&mut i, &mut line, &mut column, &chars),
+                    }
+                    continue;
+                }
+
+                if chars[i] == quote {
+                    terminated = true;
+                    break;
+                }
+                advance(&mut i, &mut line, &mut column, &chars);
+            }
+
+            if terminated {
+                advance(&mut i, &mut line, &mut column, &chars);
+            } else {
+                let kind = if quote == '`' { "template literal" } else { "string" };
+                diagnostics.push(Diagnostic::error(
+                    format!("Unterminated {}", kind),
+                    start_line,
+                    start_col,
+                ));
+            }
+            prev_significant = Some(quote);
+            continue;
+        }
+
+        // Regex literal (heuristic: only when a '/' appears where an operand is expected)
+        if c == '/' && regex_allowed(prev_significant) {
+            let (start_i, start_line, start_col) = (i, line, column);
+            advance(&mut i, &mut line, &mut column, &chars);
+            let mut in_class = false;
+            let mut closed = false;
+            while i < n && chars[i] != '\n' {
+                if chars[i] == '\\' && i + 1 < n {
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    continue;
+                }
+                if chars[i] == '[' {
+                    in_class = true;
+                } else if chars[i] == ']' {
+                    in_class = false;
+                } else if chars[i] == '/' && !in_class {
+                    advance(&mut i, &mut line, &mut column, &chars);
+                    closed = true;
+                    break;
+                }
+                advance(&mut i, &mut line, &mut column, &chars);
+            }
+            if closed {
+                while i < n && chars[i].is_ascii_alphabetic() {
+                    advance(&mut i, &mut line, &mut column, &chars);
+                }
+                prev_significant = Some('/');
+                continue;
+            }
+            // Not actually a regex literal; back off and treat '/' as an operator.
+            i = start_i;
+            line = start_line;
+            column = start_col;
+            advance(&mut i, &mut line, &mut column, &chars);
+            prev_significant = Some('/');
+            continue;
+        }
+
+        match c {
+            '{' | '(' | '[' => stack.push((c, line, column)),
+            '}' | ')' | ']' => {
+                let expected = match c {
+                    '}' => '{',
+                    ')' => '(',
+                    ']' => '[',
+                    _ => unreachable!(),
+                };
+                match stack.pop() {
+                    Some((open, _, _)) if open == expected => {}
+                    Some((open, open_line, open_col)) => diagnostics.push(Diagnostic::error(
+                        format!(
+                            "Mismatched '{}': expected the closer for '{}' opened at {}:{}",
+                            c, open, open_line, open_col
+                        ),
+                        line,
+                        column,
+                    )),
+                    None => diagnostics.push(Diagnostic::error(
+                        format!("Unexpected closing '{}' with no matching opener", c),
+                        line,
+                        column,
+                    )),
+                }
+            }
+            _ => {}
+        }
+
+        if !c.is_whitespace() {
+            prev_significant = Some(c);
+        }
+        advance(&mut i, &mut line, &mut column, &chars);
+    }
+
+    for (open, open_line, open_col) in stack {
+        diagnostics.push(Diagnostic::error(format!("Unclosed '{}'", open), open_line, open_col));
+    }
+
+    diagnostics
+}
+
 // Helper function to check if bytes are valid UTF-8
-fn is_valid_utf8(bytes: &[u8]) -> bool {
+pub(crate) fn is_valid_utf8(bytes: &[u8]) -> bool {
     String::from_utf8(bytes.to_vec()).is_ok()
 }
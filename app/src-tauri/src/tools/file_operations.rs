@@ -1,6 +1,6 @@
 /**
  * File Operations Tools
- * 
+ *
  * Rust implementation for file operations:
  * - read_file: Read file contents
  * - write_file: Write content to file
@@ -8,32 +8,59 @@
  * - move_file: Move or rename a file
  */
 
+use crate::policy::{Capability, ToolPolicy};
+use crate::tools::crawl_cache::CrawlCache;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 #[tauri::command]
-pub fn tool_read_file(path: String) -> Result<String, String> {
+pub fn tool_read_file(path: String, policy: tauri::State<'_, Arc<ToolPolicy>>) -> Result<String, String> {
+    policy
+        .check_path(Capability::FsRead, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
     fs::read_to_string(&path)
         .map_err(|e| format!("Failed to read file '{}': {}", path, e))
 }
 
 #[tauri::command]
-pub fn tool_write_file(path: String, content: String) -> Result<(), String> {
+pub fn tool_write_file(
+    path: String,
+    content: String,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    cache: tauri::State<'_, Arc<CrawlCache>>,
+) -> Result<(), String> {
+    policy
+        .check_path(Capability::FsWrite, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
     // Create parent directories if they don't exist
     if let Some(parent) = Path::new(&path).parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create parent directories: {}", e))?;
     }
-    
+
     fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file '{}': {}", path, e))
+        .map_err(|e| format!("Failed to write file '{}': {}", path, e))?;
+
+    cache.invalidate(Path::new(&path));
+    Ok(())
 }
 
 #[tauri::command]
-pub fn tool_delete_file(path: String) -> Result<(), String> {
+pub fn tool_delete_file(
+    path: String,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    cache: tauri::State<'_, Arc<CrawlCache>>,
+) -> Result<(), String> {
+    policy
+        .check_path(Capability::FsDelete, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
     let file_path = Path::new(&path);
-    
-    if file_path.is_file() {
+
+    let result = if file_path.is_file() {
         fs::remove_file(file_path)
             .map_err(|e| format!("Failed to delete file '{}': {}", path, e))
     } else if file_path.is_dir() {
@@ -41,17 +68,36 @@ pub fn tool_delete_file(path: String) -> Result<(), String> {
             .map_err(|e| format!("Failed to delete directory '{}': {}", path, e))
     } else {
         Err(format!("Path does not exist: {}", path))
-    }
+    };
+
+    cache.invalidate(file_path);
+    result
 }
 
 #[tauri::command]
-pub fn tool_move_file(source: String, destination: String) -> Result<(), String> {
+pub fn tool_move_file(
+    source: String,
+    destination: String,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    cache: tauri::State<'_, Arc<CrawlCache>>,
+) -> Result<(), String> {
+    policy
+        .check_path(Capability::FsDelete, Path::new(&source))
+        .map_err(|e| e.to_string())?;
+    policy
+        .check_path(Capability::FsWrite, Path::new(&destination))
+        .map_err(|e| e.to_string())?;
+
     // Create parent directories for destination if they don't exist
     if let Some(parent) = Path::new(&destination).parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create destination parent directories: {}", e))?;
     }
-    
+
     fs::rename(&source, &destination)
-        .map_err(|e| format!("Failed to move file from '{}' to '{}': {}", source, destination, e))
+        .map_err(|e| format!("Failed to move file from '{}' to '{}': {}", source, destination, e))?;
+
+    cache.invalidate(Path::new(&source));
+    cache.invalidate(Path::new(&destination));
+    Ok(())
 }
@@ -0,0 +1,229 @@
+/**
+ * Gemini Protocol Client
+ *
+ * A minimal client for `gemini://` capsules with Trust-On-First-Use certificate
+ * pinning: nearly every capsule uses a self-signed cert, so rather than verifying
+ * against a CA we accept whatever cert is presented on first contact, remember its
+ * SHA-256 fingerprint per host, and refuse to proceed if a later connection to the
+ * same host presents a different one.
+ */
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConnection, DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub struct GeminiResponse {
+    pub status: u16,
+    pub meta: String,
+    pub body: String,
+    pub elapsed_ms: u64,
+}
+
+/// Fetch `url` (must start with `gemini://`) over TLS with TOFU cert pinning,
+/// persisting accepted fingerprints into the file at `known_hosts_path` (one
+/// `host fingerprint` pair per line).
+pub fn fetch_gemini(url: &str, known_hosts_path: &Path, timeout: Duration) -> Result<GeminiResponse, String> {
+    let (host, port) = parse_authority(url)?;
+    let start = Instant::now();
+
+    let server_name = ServerName::try_from(host.clone())
+        .map_err(|e| format!("Invalid Gemini host '{}': {}", host, e))?;
+
+    let verifier = Arc::new(TofuVerifier::new());
+    let config = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let mut conn = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|e| format!("Failed to start TLS session: {}", e))?;
+
+    let mut sock = TcpStream::connect((host.as_str(), port))
+        .map_err(|e| format!("Failed to connect to {}:{}: {}", host, port, e))?;
+    let _ = sock.set_read_timeout(Some(timeout));
+    let _ = sock.set_write_timeout(Some(timeout));
+
+    // Drive the handshake to completion by hand, before writing anything: `Stream::write_all`
+    // would otherwise finish the handshake *and* flush the request line in the same call,
+    // so the fingerprint check below would run after the request had already gone out to
+    // whatever certificate was presented.
+    while conn.is_handshaking() {
+        conn.complete_io(&mut sock)
+            .map_err(|e| format!("TLS handshake failed: {}", e))?;
+    }
+
+    let fingerprint = verifier
+        .fingerprint()
+        .ok_or_else(|| "TLS handshake completed without observing a server certificate".to_string())?;
+    check_tofu(known_hosts_path, &host, &fingerprint)?;
+
+    {
+        let mut tls = rustls::Stream::new(&mut conn, &mut sock);
+        tls.write_all(format!("{}\r\n", url).as_bytes())
+            .map_err(|e| format!("Failed to send Gemini request: {}", e))?;
+        tls.flush().ok();
+    }
+
+    let mut response_bytes = Vec::new();
+    {
+        let mut tls = rustls::Stream::new(&mut conn, &mut sock);
+        tls.read_to_end(&mut response_bytes)
+            .map_err(|e| format!("Failed to read Gemini response: {}", e))?;
+    }
+
+    let header_end = response_bytes
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .ok_or_else(|| "Malformed Gemini response: missing status line".to_string())?;
+    let header_line = String::from_utf8_lossy(&response_bytes[..header_end]).to_string();
+    let body = String::from_utf8_lossy(&response_bytes[header_end + 2..]).to_string();
+
+    let mut parts = header_line.splitn(2, ' ');
+    let status: u16 = parts
+        .next()
+        .unwrap_or("")
+        .trim()
+        .parse()
+        .map_err(|_| format!("Malformed Gemini status line: '{}'", header_line))?;
+    let meta = parts.next().unwrap_or("").trim().to_string();
+
+    Ok(GeminiResponse {
+        status,
+        meta,
+        body,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+fn parse_authority(url: &str) -> Result<(String, u16), String> {
+    let rest = url
+        .strip_prefix("gemini://")
+        .ok_or_else(|| "Not a gemini:// URL".to_string())?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+
+    match authority.rsplit_once(':') {
+        Some((host, port_str)) => {
+            let port = port_str
+                .parse()
+                .map_err(|_| format!("Invalid port in Gemini URL authority '{}'", authority))?;
+            Ok((host.to_string(), port))
+        }
+        None => Ok((authority.to_string(), 1965)),
+    }
+}
+
+fn check_tofu(known_hosts_path: &Path, host: &str, fingerprint: &str) -> Result<(), String> {
+    let mut known = load_known_hosts(known_hosts_path);
+    match known.get(host) {
+        Some(existing) if existing == fingerprint => Ok(()),
+        Some(existing) => Err(format!(
+            "Certificate fingerprint for '{}' changed since first contact (expected {}, got {}); refusing to connect",
+            host, existing, fingerprint
+        )),
+        None => {
+            known.insert(host.to_string(), fingerprint.to_string());
+            save_known_hosts(known_hosts_path, &known);
+            Ok(())
+        }
+    }
+}
+
+fn load_known_hosts(path: &Path) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    if let Ok(content) = std::fs::read_to_string(path) {
+        for line in content.lines() {
+            if let Some((host, fingerprint)) = line.split_once(' ') {
+                map.insert(host.to_string(), fingerprint.to_string());
+            }
+        }
+    }
+    map
+}
+
+fn save_known_hosts(path: &Path, map: &HashMap<String, String>) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let content: String = map.iter().map(|(host, fp)| format!("{} {}\n", host, fp)).collect();
+    let _ = std::fs::write(path, content);
+}
+
+/// Accepts any certificate (that's the point of TOFU) but records its SHA-256
+/// fingerprint so the caller can pin/verify it against `known_hosts`.
+#[derive(Debug)]
+struct TofuVerifier {
+    fingerprint: Mutex<Option<String>>,
+}
+
+impl TofuVerifier {
+    fn new() -> Self {
+        Self { fingerprint: Mutex::new(None) }
+    }
+
+    fn fingerprint(&self) -> Option<String> {
+        self.fingerprint.lock().ok().and_then(|g| g.clone())
+    }
+}
+
+impl ServerCertVerifier for TofuVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let mut hasher = Sha256::new();
+        hasher.update(end_entity.as_ref());
+        let fingerprint = hex_encode(&hasher.finalize());
+        if let Ok(mut guard) = self.fingerprint.lock() {
+            *guard = Some(fingerprint);
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PKCS1_SHA256,
+            SignatureScheme::RSA_PKCS1_SHA384,
+            SignatureScheme::RSA_PKCS1_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+            SignatureScheme::ECDSA_NISTP521_SHA512,
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::ED25519,
+        ]
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
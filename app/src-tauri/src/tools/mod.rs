@@ -1,8 +1,12 @@
 /**
  * Tool Module
- * 
+ *
  * Rust implementation of AI tools
  * Organized in modular structure for maintainability
+ *
+ * All commands that touch the filesystem, run a process, or hit the network consult
+ * the `crate::policy::ToolPolicy` managed state before acting, except
+ * `tool_file_check`, which reads its target file without a policy check.
  */
 
 pub mod file_operations;
@@ -12,6 +16,9 @@ pub mod command_operations;
 pub mod file_check;
 pub mod diff_operations;
 pub mod fetch_operations;
+pub mod crawl_cache;
+pub mod watch_operations;
+pub mod gemini_protocol;
 
 // Re-export all tool commands for easy access
 pub use file_operations::*;
@@ -21,3 +28,4 @@ pub use command_operations::*;
 pub use file_check::*;
 pub use diff_operations::*;
 pub use fetch_operations::*;
+pub use watch_operations::*;
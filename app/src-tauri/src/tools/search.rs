@@ -1,36 +1,215 @@
 /**
  * Search Tools
- * 
+ *
  * Rust implementation for search operations:
  * - search_files: Search for files by glob pattern
+ * - search_content: Grep-style content search across files (regex or fixed strings)
  */
 
-use glob::glob;
+use crate::policy::{Capability, ToolPolicy};
+use crate::tools::crawl_cache::{walk_entries, CrawlCache, DirEntry, WalkOptions};
+use crate::tools::file_check::is_valid_utf8;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
 
 #[tauri::command]
-pub fn tool_search_files(path: String, pattern: String) -> Result<Vec<String>, String> {
-    // Construct the full search pattern
-    let search_pattern = if path.ends_with('/') || path.ends_with('\\') {
-        format!("{}{}", path, pattern)
-    } else {
-        format!("{}/{}", path, pattern)
+pub fn tool_search_files(
+    path: String,
+    pattern: String,
+    respect_gitignore: Option<bool>,
+    include_hidden: Option<bool>,
+    max_depth: Option<usize>,
+    extensions: Option<Vec<String>>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    cache: tauri::State<'_, Arc<CrawlCache>>,
+) -> Result<Vec<DirEntry>, String> {
+    policy
+        .check_path(Capability::FsRead, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
+    let dir_path = Path::new(&path);
+
+    if !dir_path.exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    let glob_pattern = glob::Pattern::new(&pattern)
+        .map_err(|e| format!("Invalid glob pattern '{}': {}", pattern, e))?;
+
+    let options = WalkOptions {
+        recursive: true,
+        respect_gitignore: respect_gitignore.unwrap_or(true),
+        include_hidden: include_hidden.unwrap_or(false),
+        max_depth,
+        extensions,
     };
-    
+
+    let entries = cache.get_or_walk(dir_path, &options)?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            let file_name = entry.path.rsplit('/').next().unwrap_or(&entry.path);
+            glob_pattern.matches(&entry.path) || glob_pattern.matches(file_name)
+        })
+        .collect())
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchOptions {
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    #[serde(default)]
+    pub context_lines: Option<usize>,
+    /// When set, match these fixed strings with aho-corasick instead of treating
+    /// `pattern` as a regex.
+    #[serde(default)]
+    pub literals: Option<Vec<String>>,
+    #[serde(default)]
+    pub respect_gitignore: Option<bool>,
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ContentMatch {
+    pub file: String,
+    pub line_number: usize,
+    pub line_text: String,
+    pub byte_offset: usize,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+enum ContentMatcher {
+    Regex(regex::Regex),
+    Literals(aho_corasick::AhoCorasick),
+}
+
+impl ContentMatcher {
+    fn regex(pattern: &str, case_insensitive: bool, whole_word: bool) -> Result<Self, String> {
+        let pattern = if whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern.to_string()
+        };
+
+        regex::RegexBuilder::new(&pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map(ContentMatcher::Regex)
+            .map_err(|e| format!("Invalid regex pattern '{}': {}", pattern, e))
+    }
+
+    fn literals(literals: &[String], case_insensitive: bool) -> Result<Self, String> {
+        aho_corasick::AhoCorasickBuilder::new()
+            .ascii_case_insensitive(case_insensitive)
+            .build(literals)
+            .map(ContentMatcher::Literals)
+            .map_err(|e| format!("Invalid literal pattern set: {}", e))
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            ContentMatcher::Regex(re) => re.is_match(line),
+            ContentMatcher::Literals(ac) => ac.is_match(line),
+        }
+    }
+}
+
+/// Grep-style content search: walks `path` (reusing the `.gitignore`-aware walker)
+/// and returns every matching line as `{file, line_number, line_text, byte_offset}`,
+/// skipping binary files with the same UTF-8 detection used by `tool_file_check`.
+#[tauri::command]
+pub fn tool_search_content(
+    path: String,
+    pattern: String,
+    options: Option<ContentSearchOptions>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+) -> Result<Vec<ContentMatch>, String> {
+    policy
+        .check_path(Capability::FsRead, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
+    let root = Path::new(&path);
+    if !root.exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    let options = options.unwrap_or_default();
+
+    let matcher = match &options.literals {
+        Some(literals) => ContentMatcher::literals(literals, options.case_insensitive)?,
+        None => ContentMatcher::regex(&pattern, options.case_insensitive, options.whole_word)?,
+    };
+
+    let walk_options = WalkOptions {
+        recursive: true,
+        respect_gitignore: options.respect_gitignore.unwrap_or(true),
+        include_hidden: options.include_hidden.unwrap_or(false),
+        max_depth: options.max_depth,
+        extensions: None,
+    };
+    let entries = walk_entries(root, &walk_options)?;
+
+    let context = options.context_lines.unwrap_or(0);
+    let max_results = options.max_results.unwrap_or(usize::MAX);
+
     let mut results = Vec::new();
-    
-    for entry in glob(&search_pattern)
-        .map_err(|e| format!("Invalid glob pattern '{}': {}", search_pattern, e))? 
-    {
-        match entry {
-            Ok(path) => {
-                results.push(path.display().to_string());
-            },
-            Err(e) => {
-                eprintln!("Error reading glob entry: {}", e);
-                // Continue processing other entries
+    'files: for entry in entries {
+        if entry.is_dir {
+            continue;
+        }
+
+        let bytes = match fs::read(root.join(&entry.path)) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        if !is_valid_utf8(&bytes) {
+            continue;
+        }
+
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let mut byte_offset = 0usize;
+        for (idx, line) in lines.iter().enumerate() {
+            if matcher.is_match(line) {
+                let context_before = lines[idx.saturating_sub(context)..idx]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                let after_end = (idx + 1 + context).min(lines.len());
+                let context_after = lines[idx + 1..after_end]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+
+                results.push(ContentMatch {
+                    file: entry.path.clone(),
+                    line_number: idx + 1,
+                    line_text: line.to_string(),
+                    byte_offset,
+                    context_before,
+                    context_after,
+                });
+
+                if results.len() >= max_results {
+                    break 'files;
+                }
             }
+
+            byte_offset += line.len() + 1;
         }
     }
-    
+
     Ok(results)
 }
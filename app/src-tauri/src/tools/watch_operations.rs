@@ -0,0 +1,259 @@
+/**
+ * Filesystem Watch Tools
+ *
+ * Lets a session subscribe to changes under a directory and receive batched events
+ * through the existing agent messaging pipeline (`AgentStateManager::send_user_message`).
+ *
+ * Modeled on Deno's `--watch`: the root is resolved to an absolute path up front so a
+ * later chdir can't break it, and rapid bursts of events are coalesced with a debounce
+ * window into a single batched create/modify/remove report.
+ */
+
+use crate::agent::{AgentMessageType, AgentStateManager};
+use crate::policy::{Capability, ToolPolicy};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+struct ActiveWatcher {
+    // Keeps the OS watch alive for as long as the session is subscribed.
+    _watcher: RecommendedWatcher,
+    stop_tx: Sender<()>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Active watchers keyed by `session_id`, parallel to `AgentStateManager`.
+pub struct WatcherRegistry {
+    watchers: Mutex<HashMap<String, ActiveWatcher>>,
+}
+
+impl WatcherRegistry {
+    pub fn new() -> Self {
+        Self {
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn start(
+        &self,
+        session_id: String,
+        path: String,
+        recursive: bool,
+        debounce_ms: u64,
+        agent_state: Arc<AgentStateManager>,
+    ) -> Result<(), String> {
+        // Replace any existing watcher for this session rather than leaking it.
+        self.stop(&session_id);
+
+        let root = Path::new(&path)
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve watch root '{}': {}", path, e))?;
+
+        let (event_tx, event_rx) = channel::<notify::Result<Event>>();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = event_tx.send(res);
+        })
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(&root, mode)
+            .map_err(|e| format!("Failed to watch '{}': {}", root.display(), e))?;
+
+        let (stop_tx, stop_rx) = channel::<()>();
+        let debounce = Duration::from_millis(debounce_ms.max(1));
+        let session_for_thread = session_id.clone();
+
+        let handle = std::thread::spawn(move || {
+            let mut created = HashSet::new();
+            let mut modified = HashSet::new();
+            let mut removed = HashSet::new();
+
+            loop {
+                if stop_rx.try_recv().is_ok() {
+                    break;
+                }
+
+                match event_rx.recv_timeout(debounce) {
+                    Ok(Ok(event)) => {
+                        categorize(&event, &mut created, &mut modified, &mut removed);
+                        let stopped = drain_until_quiet(
+                            &event_rx,
+                            &stop_rx,
+                            debounce,
+                            &mut created,
+                            &mut modified,
+                            &mut removed,
+                        );
+                        flush_batch(&agent_state, &session_for_thread, &mut created, &mut modified, &mut removed);
+                        if stopped {
+                            break;
+                        }
+                    }
+                    Ok(Err(_)) => continue,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        self.watchers.lock().map_err(|e| e.to_string())?.insert(
+            session_id,
+            ActiveWatcher {
+                _watcher: watcher,
+                stop_tx,
+                handle: Some(handle),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Tear down the watcher for `session_id`, if any. Safe to call when none exists.
+    pub fn stop(&self, session_id: &str) {
+        let active = self.watchers.lock().ok().and_then(|mut map| map.remove(session_id));
+        if let Some(mut active) = active {
+            let _ = active.stop_tx.send(());
+            if let Some(handle) = active.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+/// Keep absorbing events until the channel has been quiet for a full debounce window,
+/// or `stop_rx` fires — checked every iteration so continuous churn (e.g. a build writing
+/// into the watched directory) can't stall teardown until the churn itself goes quiet.
+/// Returns `true` if a stop was observed.
+fn drain_until_quiet(
+    event_rx: &std::sync::mpsc::Receiver<notify::Result<Event>>,
+    stop_rx: &std::sync::mpsc::Receiver<()>,
+    debounce: Duration,
+    created: &mut HashSet<String>,
+    modified: &mut HashSet<String>,
+    removed: &mut HashSet<String>,
+) -> bool {
+    loop {
+        if stop_rx.try_recv().is_ok() {
+            return true;
+        }
+
+        match event_rx.recv_timeout(debounce) {
+            Ok(Ok(event)) => categorize(&event, created, modified, removed),
+            Ok(Err(_)) => continue,
+            Err(_) => return false,
+        }
+    }
+}
+
+fn categorize(
+    event: &Event,
+    created: &mut HashSet<String>,
+    modified: &mut HashSet<String>,
+    removed: &mut HashSet<String>,
+) {
+    let paths = event
+        .paths
+        .iter()
+        .map(|p| p.to_string_lossy().replace('\\', "/"));
+
+    match event.kind {
+        EventKind::Create(_) => paths.for_each(|p| {
+            created.insert(p);
+        }),
+        EventKind::Remove(_) => paths.for_each(|p| {
+            removed.insert(p);
+        }),
+        EventKind::Modify(_) => paths.for_each(|p| {
+            modified.insert(p);
+        }),
+        _ => {}
+    }
+}
+
+fn flush_batch(
+    agent_state: &Arc<AgentStateManager>,
+    session_id: &str,
+    created: &mut HashSet<String>,
+    modified: &mut HashSet<String>,
+    removed: &mut HashSet<String>,
+) {
+    if created.is_empty() && modified.is_empty() && removed.is_empty() {
+        return;
+    }
+
+    let markdown = format_batch(created, modified, removed);
+    let _ = agent_state.send_user_message(session_id.to_string(), markdown, AgentMessageType::Info);
+
+    created.clear();
+    modified.clear();
+    removed.clear();
+}
+
+fn format_batch(
+    created: &HashSet<String>,
+    modified: &HashSet<String>,
+    removed: &HashSet<String>,
+) -> String {
+    let mut sections = Vec::new();
+    if !created.is_empty() {
+        sections.push(format!("**Created:** {}", join_sorted(created)));
+    }
+    if !modified.is_empty() {
+        sections.push(format!("**Modified:** {}", join_sorted(modified)));
+    }
+    if !removed.is_empty() {
+        sections.push(format!("**Removed:** {}", join_sorted(removed)));
+    }
+    sections.join("\n")
+}
+
+fn join_sorted(paths: &HashSet<String>) -> String {
+    let mut sorted: Vec<&String> = paths.iter().collect();
+    sorted.sort();
+    sorted
+        .iter()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[tauri::command]
+pub fn tool_watch_start(
+    session_id: String,
+    path: String,
+    recursive: bool,
+    debounce_ms: Option<u64>,
+    policy: tauri::State<'_, Arc<ToolPolicy>>,
+    registry: tauri::State<'_, Arc<WatcherRegistry>>,
+    agent_state: tauri::State<'_, Arc<AgentStateManager>>,
+) -> Result<(), String> {
+    policy
+        .check_path(Capability::FsRead, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
+    registry.start(
+        session_id,
+        path,
+        recursive,
+        debounce_ms.unwrap_or(200),
+        agent_state.inner().clone(),
+    )
+}
+
+#[tauri::command]
+pub fn tool_watch_stop(
+    session_id: String,
+    registry: tauri::State<'_, Arc<WatcherRegistry>>,
+) -> Result<(), String> {
+    registry.stop(&session_id);
+    Ok(())
+}